@@ -1,7 +1,42 @@
-use std::{collections::{HashMap, HashSet}, hash::Hash};
+use std::{collections::HashMap, hash::Hash};
 
 use crate::multiset::MultiSet;
 
+/// A `HashMap` that also remembers the order keys were first inserted in, so grouping
+/// operators can process (and emit) groups in first-seen order instead of an arbitrary
+/// hash order. Used by `reduce` so its ordering guarantee doesn't depend on sorting
+/// the output afterward.
+struct InsertionOrderedMap<K, V> {
+    order: Vec<K>,
+    entries: HashMap<K, V>,
+}
+
+impl<K: Eq + Hash + Clone, V> InsertionOrderedMap<K, V> {
+    fn new() -> Self {
+        InsertionOrderedMap {
+            order: vec![],
+            entries: HashMap::new(),
+        }
+    }
+
+    fn entry_or_insert_with(&mut self, key: K, default: impl FnOnce() -> V) -> &mut V {
+        if !self.entries.contains_key(&key) {
+            self.order.push(key.clone());
+            self.entries.insert(key.clone(), default());
+        }
+        self.entries.get_mut(&key).expect("just inserted")
+    }
+
+    /// Consumes the map, yielding `(key, value)` pairs in first-seen order.
+    fn into_iter_in_order(self) -> impl Iterator<Item = (K, V)> {
+        let InsertionOrderedMap { order, mut entries } = self;
+        order.into_iter().map(move |key| {
+            let value = entries.remove(&key).expect("key tracked in `order`");
+            (key, value)
+        })
+    }
+}
+
 /// A collection of `MultiSet`s, where each `MultiSet` represents a record and its multiplicity.
 #[derive(Debug, Clone)]
 pub struct  Collection<T: Ord>(pub Vec<MultiSet<T>>);
@@ -143,6 +178,13 @@ where
     /// a collection containing `(key, f(values associated with key))`. We can also define functions
     /// built on top of `reduce`, seen below.
     ///
+    /// # Ordering
+    ///
+    /// Keys are grouped and processed in first-seen order (the order their first record
+    /// appears in `self`), not hash order, so `f` is invoked deterministically and runs
+    /// are reproducible. `count`, `sum`, `distinct`, and `consolidate` inherit this
+    /// guarantee since they're built on `reduce`.
+    ///
     /// # Examples
     ///
     /// ```
@@ -169,33 +211,67 @@ where
         F: Fn(Vec<(T, i32)>) -> Vec<(T, i32)>,
         T: Eq + std::hash::Hash,
     {
-        let mut keys: HashMap<T, Vec<(T, i32)>> = HashMap::new();
+        let mut keys: InsertionOrderedMap<T, Vec<(T, i32)>> = InsertionOrderedMap::new();
 
         for multi_set in &self.0 {
-            let entry = keys.entry(multi_set.record.clone()).or_default();
+            let entry = keys.entry_or_insert_with(multi_set.record.clone(), Vec::new);
             entry.push((multi_set.record.clone(), multi_set.multiplicity));
         }
 
         let mut out = vec![];
-        for (_key, vals) in keys {
+        for (_key, vals) in keys.into_iter_in_order() {
             let results = f(vals);
             for (val, multiplicity) in results {
                 out.push(MultiSet::new(val, multiplicity));
             }
         }
 
-        // Sort the resulting Collection by record and multiplicity
-        out.sort_unstable_by(|a, b| {
-            a.record
-                .cmp(&b.record)
-                .then(a.multiplicity.cmp(&b.multiplicity))
-        });
-
         Collection(out)
     }
 
+    /// A one-pass keyed aggregation: groups by record identity the same way `reduce`
+    /// does, folds every `(record, multiplicity)` pair for a key into an accumulator
+    /// starting from `init`, then calls `finish` with the key and the final accumulator
+    /// to produce that key's output rows. `count`, `sum`, and `mean` are all thin
+    /// wrappers around this, so new per-key statistics don't need to re-implement
+    /// `reduce`'s grouping.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rs_differential_dataflow::collection::Collection;
+    /// use rs_differential_dataflow::multiset::MultiSet;
+    ///
+    /// let coll = Collection(vec![
+    ///     MultiSet::new("a".to_string(), 1),
+    ///     MultiSet::new("a".to_string(), 3),
+    /// ]);
+    /// let result = coll.group_aggregate(
+    ///     0,
+    ///     |acc, (_, multiplicity)| acc + multiplicity,
+    ///     |key, acc| vec![(key.clone(), acc)],
+    /// );
+    /// assert_eq!(result, Collection(vec![MultiSet::new("a".to_string(), 4)]));
+    /// ```
+    pub fn group_aggregate<A, Fold, Fin>(&self, init: A, fold: Fold, finish: Fin) -> Collection<T>
+    where
+        T: Hash,
+        A: Clone,
+        Fold: Fn(A, &(T, i32)) -> A,
+        Fin: Fn(&T, A) -> Vec<(T, i32)>,
+    {
+        self.reduce(|vals| {
+            let key = vals[0].0.clone();
+            let acc = vals.iter().fold(init.clone(), &fold);
+            finish(&key, acc)
+        })
+    }
+
     /// Returns the number of values associated with each key.
     ///
+    /// Built on `reduce`, so keys are processed (and emitted) in first-seen order; see
+    /// its "Ordering" section.
+    ///
     /// # Examples
     ///
     /// ```
@@ -215,18 +291,17 @@ where
     /// ]));
     /// ```
     pub fn count(&self) -> Collection<T>
-    where 
+    where
         T: Hash
     {
-        let reduced = self.reduce(|vals| {
-            let count = vals.len() as i32;
-            vec![(vals[0].0.clone(), count)]
-        });
-        reduced
+        self.group_aggregate(0, |acc, _| acc + 1, |key, acc| vec![(key.clone(), acc)])
     }
 
     /// Returns the sum of the values associated with each key.
     ///
+    /// Built on `reduce`, so keys are processed (and emitted) in first-seen order; see
+    /// its "Ordering" section.
+    ///
     /// # Examples
     ///
     /// ```
@@ -245,24 +320,53 @@ where
     ///     MultiSet::new("b".to_string(), 6),
     /// ]));
     /// ```
-    pub fn sum(&self) -> Collection<T> 
-    where 
+    pub fn sum(&self) -> Collection<T>
+    where
         T: Hash
     {
-        let reduced = self.reduce(|vals| {
-            let sum = vals
-                .iter()
-                .map(|(_, multiplicity)| multiplicity).sum();
-            vec![(vals[0].0.clone(), sum)]
-        });
-        reduced
+        self.group_aggregate(0, |acc, (_, m)| acc + m, |key, acc| vec![(key.clone(), acc)])
+    }
+
+    /// Returns the average multiplicity associated with each key, rounded toward zero.
+    /// A key with no values produces no output row.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rs_differential_dataflow::collection::Collection;
+    /// use rs_differential_dataflow::multiset::MultiSet;
+    ///
+    /// let coll = Collection(vec![
+    ///     MultiSet::new("a".to_string(), 1),
+    ///     MultiSet::new("a".to_string(), 3),
+    /// ]);
+    /// let result = coll.mean();
+    /// assert_eq!(result, Collection(vec![MultiSet::new("a".to_string(), 2)]));
+    /// ```
+    pub fn mean(&self) -> Collection<T>
+    where
+        T: Hash,
+    {
+        self.group_aggregate(
+            (0, 0),
+            |(sum, count), (_, m)| (sum + m, count + 1),
+            |key, (sum, count)| {
+                if count == 0 {
+                    vec![]
+                } else {
+                    vec![(key.clone(), sum / count)]
+                }
+            },
+        )
     }
 
     /// Returns a collection containing the distinct set of values associated with each key.
     ///
-    /// This function groups elements in the collection by their keys, and then returns a new collection
-    /// where each distinct value associated with each key is represented exactly once. The resulting
-    /// collection is unordered.
+    /// This function groups elements in the collection by their keys, sums each key's
+    /// multiplicities, and emits the key once with multiplicity `1` if that net total
+    /// is strictly positive. Keys whose net total is zero or negative are dropped, so
+    /// a retraction (a negative multiplicity) can cancel a record out of the distinct
+    /// set instead of silently surviving alongside it.
     ///
     /// # Examples
     ///
@@ -274,39 +378,40 @@ where
     ///     MultiSet::new("a".to_string(), 1),
     ///     MultiSet::new("b".to_string(), 2),
     ///     MultiSet::new("a".to_string(), 3),
-    ///     MultiSet::new("b".to_string(), 4),
+    ///     MultiSet::new("b".to_string(), -2),
     /// ]);
     /// let result = coll.distinct();
     /// assert_eq!(result, Collection(vec![
     ///     MultiSet::new("a".to_string(), 1),
-    ///     MultiSet::new("b".to_string(), 1),
     /// ]));
     /// ```
     ///
     /// # Notes
     ///
-    /// - The order of the elements in the resulting collection is not guaranteed.
+    /// - Built on `reduce`, so keys are processed (and emitted) in first-seen order;
+    ///   see its "Ordering" section.
     /// - If the input collection is empty, the resulting collection will also be empty.
     ///
-    pub fn distinct(&self) -> Collection<T> 
-    where 
+    pub fn distinct(&self) -> Collection<T>
+    where
         T: Hash
     {
-        let reduced = self.reduce(|vals| {
-            let mut distinct = std::collections::HashSet::new();
-            for (val, _) in vals {
-                distinct.insert(val.clone());
+        self.group_aggregate(0, |acc, (_, m)| acc + m, |key, total| {
+            if total > 0 {
+                vec![(key.clone(), 1)]
+            } else {
+                vec![]
             }
-            let out = distinct.into_iter().map(|val| (val, 1)).collect();
-            out
-        });
-        reduced
+        })
     }
 
     /// Produces a normalized, logically equivalent version of the input collection
     /// containing exactly one instance of each record, and no records with a multiplicity
     /// of 0.
     ///
+    /// Built on `reduce`, so keys are processed (and emitted) in first-seen order; see
+    /// its "Ordering" section.
+    ///
     /// # Examples
     ///
     /// ```
@@ -319,70 +424,41 @@ where
     ///     MultiSet::new("a".to_string(), -1),
     ///     MultiSet::new("a".to_string(), 2),
     /// ]);
+    /// assert_eq!(coll.consolidate(), Collection(vec![
+    ///     MultiSet::new("a".to_string(), 2),
+    /// ]));
     /// ```
-    pub fn consolidate(&self) -> Collection<T> 
-    where 
+    pub fn consolidate(&self) -> Collection<T>
+    where
         T: Hash
     {
-        // BUG: tbh I think this is wrong -- currently outputs MultiSet(record, 1) for Collection
-        let reduced = self.reduce(|vals| {
-            let mut count = 0;
-            let mut out = vec![];
-            for (record, multiplicity) in vals {
-                count += multiplicity;
-                if multiplicity > 0 && count == multiplicity {
-                    out.push((record.clone(), multiplicity));
-                }
+        self.group_aggregate(0, |acc, (_, m)| acc + m, |key, total| {
+            if total == 0 {
+                vec![]
+            } else {
+                vec![(key.clone(), total)]
             }
-            out
-        });
-        reduced
+        })
     }
 
-    /// Takes two input collections, and for all `(x, y)` in the first collection, and all
-    /// `(x, z)` in the second collection, produces `(x, (y, z))` as output.
-    ///
-    /// # Examples
-    ///
-    /// ```
-    /// use rs_differential_dataflow::collection::Collection;
-    /// use rs_differential_dataflow::multiset::MultiSet;
-    ///
-    /// let coll1 = Collection(vec![
-    ///     MultiSet::new("a".to_string(), 1),
-    ///     MultiSet::new("b".to_string(), 2),
-    /// ]);
-    /// let coll2 = Collection(vec![
-    ///     MultiSet::new("a".to_string(), 3),
-    ///     MultiSet::new("b".to_string(), 4),
-    /// ]);
-    /// let result = coll1.join(&coll2);
-    /// assert_eq!(result, Collection(vec![
-    ///     MultiSet::new("a".to_string(), 3),
-    ///     MultiSet::new("b".to_string(), 8),
-    /// ]));
-    /// ```
-    pub fn join(&self, other: &Collection<T>) -> Collection<T> 
-    where 
-        T: Hash
-    {
-        let out = self.0.iter()
-            .flat_map(|ms1| other.0.iter().filter(move |ms2| ms1.record == ms2.record)
-                .map(move |ms2| MultiSet::new(ms1.record.clone(), ms1.multiplicity * ms2.multiplicity)))
-            .collect::<Vec<_>>();
-
-        let mut records = HashSet::new();
-        let deduped_out = out.into_iter()
-            .filter(|ms| records.insert(ms.record.clone()))
-            .collect::<Vec<_>>();
-        
-        Collection(deduped_out)
-    }
+    // `join` used to live here, pairing records that were byte-for-byte equal. That's
+    // not a relational join, so it moved to `crate::keyed`, which joins `Collection<(K, V)>`
+    // on the shared key `K` the way a real differential join does.
+
+    /// The number of rounds `iterate` will run before giving up on a closure that
+    /// never reaches a fixpoint.
+    const DEFAULT_MAX_ITERS: usize = 10_000;
 
     /// This function takes one input collection and repeatedly applies a function `f` to the
     /// input until the output stops changing. `f` can be any combination of the functional
     /// operations defined withing `impl Collection`, including other nested calls to `iterate`.
     ///
+    /// Convergence is detected by consolidating `result.concat(curr.negate())`: if that
+    /// difference is empty, `result` and `curr` are logically equal even if they differ
+    /// in ordering or carry redundant zero-multiplicity rows, which a plain `==` on the
+    /// underlying vectors would miss. Runs for at most `Self::DEFAULT_MAX_ITERS` rounds;
+    /// use `iterate_with_max` to override that cap.
+    ///
     /// # Examples
     ///
     /// ```
@@ -402,25 +478,151 @@ where
     /// ]));
     /// ```
     pub fn iterate<F>(&self, f: F) -> Collection<T>
-    where 
+    where
         F: Fn(&Collection<T>) -> Collection<T>,
+        T: Hash,
+    {
+        self.iterate_with_max(f, Self::DEFAULT_MAX_ITERS)
+    }
+
+    /// Like `iterate`, but stops after at most `max_iters` rounds even if `f` never
+    /// reaches a fixpoint, returning whatever the last round produced.
+    pub fn iterate_with_max<F>(&self, f: F, max_iters: usize) -> Collection<T>
+    where
+        F: Fn(&Collection<T>) -> Collection<T>,
+        T: Hash,
     {
         let mut curr = Collection(self.0.clone());
-        loop {
+        for _ in 0..max_iters {
             let result = f(&curr);
-            if result.0 == curr.0 {
-                break;
+            let diff = result.clone().concat(curr.clone().negate()).consolidate();
+            if diff.0.is_empty() {
+                return result;
             }
             curr = result;
         }
         curr
     }
 
-    // fn min(self) -> () {
-    //     ()
-    // }
+    // `min`/`max` used to be stubbed out here. They're now real operators in
+    // `crate::keyed`, since "smallest/largest value for a key" only makes sense on
+    // `Collection<(K, V)>`.
+}
+
+/// Consolidates both collections into `record -> net multiplicity` maps, applies
+/// `combine` to every record in their key union, and keeps the records where `combine`
+/// returns a positive multiplicity. Shared by `BitOr`, `BitAnd`, and `Sub`.
+fn set_algebra<T, F>(left: Collection<T>, right: Collection<T>, combine: F) -> Collection<T>
+where
+    T: Ord + Clone + Hash,
+    F: Fn(i32, i32) -> i32,
+{
+    let left_totals: HashMap<T, i32> = left
+        .consolidate()
+        .0
+        .into_iter()
+        .map(|ms| (ms.record, ms.multiplicity))
+        .collect();
+    let right_totals: HashMap<T, i32> = right
+        .consolidate()
+        .0
+        .into_iter()
+        .map(|ms| (ms.record, ms.multiplicity))
+        .collect();
+
+    let keys: std::collections::HashSet<T> = left_totals
+        .keys()
+        .chain(right_totals.keys())
+        .cloned()
+        .collect();
+
+    let out = keys
+        .into_iter()
+        .filter_map(|key| {
+            let lhs = left_totals.get(&key).copied().unwrap_or(0);
+            let rhs = right_totals.get(&key).copied().unwrap_or(0);
+            let multiplicity = combine(lhs, rhs);
+            (multiplicity > 0).then(|| MultiSet::new(key, multiplicity))
+        })
+        .collect();
+    Collection(out)
+}
 
-    // fn max(self) -> () {
-    //     ()
-    // }
+impl<T: Ord + Clone + Hash> Collection<T> {
+    /// `true` if no record has positive net multiplicity in both `self` and `other`.
+    pub fn is_disjoint(&self, other: &Collection<T>) -> bool {
+        let left: HashMap<T, i32> = self
+            .consolidate()
+            .0
+            .into_iter()
+            .map(|ms| (ms.record, ms.multiplicity))
+            .collect();
+        other
+            .consolidate()
+            .0
+            .into_iter()
+            .all(|ms| ms.multiplicity <= 0 || left.get(&ms.record).copied().unwrap_or(0) <= 0)
+    }
+}
+
+/// Multiset union: each record's multiplicity is the larger of its multiplicity in
+/// either side.
+///
+/// # Examples
+///
+/// ```
+/// use rs_differential_dataflow::collection::Collection;
+/// use rs_differential_dataflow::multiset::MultiSet;
+///
+/// let a = Collection(vec![MultiSet::new("x".to_string(), 1)]);
+/// let b = Collection(vec![MultiSet::new("x".to_string(), 3)]);
+/// assert_eq!(a | b, Collection(vec![MultiSet::new("x".to_string(), 3)]));
+/// ```
+impl<T: Ord + Clone + Hash> std::ops::BitOr for Collection<T> {
+    type Output = Collection<T>;
+
+    fn bitor(self, rhs: Collection<T>) -> Collection<T> {
+        set_algebra(self, rhs, |a, b| a.max(b))
+    }
+}
+
+/// Multiset intersection: each record's multiplicity is the smaller of its
+/// multiplicity in either side.
+///
+/// # Examples
+///
+/// ```
+/// use rs_differential_dataflow::collection::Collection;
+/// use rs_differential_dataflow::multiset::MultiSet;
+///
+/// let a = Collection(vec![MultiSet::new("x".to_string(), 1)]);
+/// let b = Collection(vec![MultiSet::new("x".to_string(), 3)]);
+/// assert_eq!(a & b, Collection(vec![MultiSet::new("x".to_string(), 1)]));
+/// ```
+impl<T: Ord + Clone + Hash> std::ops::BitAnd for Collection<T> {
+    type Output = Collection<T>;
+
+    fn bitand(self, rhs: Collection<T>) -> Collection<T> {
+        set_algebra(self, rhs, |a, b| a.min(b))
+    }
+}
+
+/// Multiset difference: each record's multiplicity is `max(0, self - other)`.
+///
+/// # Examples
+///
+/// ```
+/// use rs_differential_dataflow::collection::Collection;
+/// use rs_differential_dataflow::multiset::MultiSet;
+///
+/// let a = Collection(vec![MultiSet::new("x".to_string(), 3)]);
+/// let b = Collection(vec![MultiSet::new("x".to_string(), 1)]);
+/// assert_eq!(a - b, Collection(vec![MultiSet::new("x".to_string(), 2)]));
+/// ```
+impl<T: Ord + Clone + Hash> std::ops::Sub for Collection<T> {
+    type Output = Collection<T>;
+
+    fn sub(self, rhs: Collection<T>) -> Collection<T> {
+        set_algebra(self, rhs, |a, b| (a - b).max(0))
+    }
 }
\ No newline at end of file
@@ -0,0 +1,245 @@
+use std::{collections::HashMap, hash::Hash};
+
+use crate::collection::Collection;
+use crate::multiset::MultiSet;
+
+/// Operators specific to collections of `(key, value)` records, grouped by their
+/// shared key the same way [`Collection::reduce`] groups by record identity.
+impl<K, V1> Collection<(K, V1)>
+where
+    K: Ord + Clone + Eq + Hash,
+    V1: Ord + Clone + Hash,
+{
+    /// Groups the collection's entries by key, preserving each key's `(value,
+    /// multiplicity)` pairs.
+    fn group_by_key(&self) -> HashMap<K, Vec<(V1, i32)>> {
+        let mut groups: HashMap<K, Vec<(V1, i32)>> = HashMap::new();
+        for ms in &self.0 {
+            let (key, value) = ms.record.clone();
+            groups.entry(key).or_default().push((value, ms.multiplicity));
+        }
+        groups
+    }
+
+    /// A relational inner join on the shared key `K`: for all `(k, v1)` in `self` and
+    /// `(k, v2)` in `other` sharing `k`, emits `(k, (v1, v2))` with multiplicity equal
+    /// to the product of the two input multiplicities, summing multiplicities when the
+    /// same output tuple arises from more than one pairing. Output rows are sorted by
+    /// `record`, the same ordering guarantee as `reduce_by_key`, `min`/`max`, and
+    /// `distinct_by_key`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rs_differential_dataflow::collection::Collection;
+    /// use rs_differential_dataflow::multiset::MultiSet;
+    ///
+    /// let left = Collection(vec![
+    ///     MultiSet::new((1, "a".to_string()), 2),
+    ///     MultiSet::new((2, "b".to_string()), 1),
+    /// ]);
+    /// let right = Collection(vec![
+    ///     MultiSet::new((1, "x".to_string()), 3),
+    /// ]);
+    /// let result = left.join(&right);
+    /// assert_eq!(result, Collection(vec![
+    ///     MultiSet::new((1, ("a".to_string(), "x".to_string())), 6),
+    /// ]));
+    /// ```
+    pub fn join<V2>(&self, other: &Collection<(K, V2)>) -> Collection<(K, (V1, V2))>
+    where
+        V2: Ord + Clone + Eq + Hash,
+    {
+        let left = self.group_by_key();
+        let right = other.group_by_key();
+
+        let mut totals: HashMap<(K, (V1, V2)), i32> = HashMap::new();
+        for (key, v1s) in &left {
+            if let Some(v2s) = right.get(key) {
+                for (v1, m1) in v1s {
+                    for (v2, m2) in v2s {
+                        let record = (key.clone(), (v1.clone(), v2.clone()));
+                        *totals.entry(record).or_insert(0) += m1 * m2;
+                    }
+                }
+            }
+        }
+
+        let mut out: Vec<_> = totals
+            .into_iter()
+            .filter(|(_, multiplicity)| *multiplicity != 0)
+            .map(|(record, multiplicity)| MultiSet::new(record, multiplicity))
+            .collect();
+        out.sort_unstable_by(|a, b| a.record.cmp(&b.record));
+        Collection(out)
+    }
+
+    /// Keeps the `(k, v1)` entries of `self` whose key `k` has positive net
+    /// multiplicity in `other`, leaving their own multiplicities untouched.
+    pub fn semijoin<V2>(&self, other: &Collection<(K, V2)>) -> Collection<(K, V1)>
+    where
+        V2: Ord + Clone + Eq + Hash,
+    {
+        let present = Self::keys_with_positive_weight(&other.group_by_key());
+        let out = self
+            .0
+            .iter()
+            .filter(|ms| present.contains(&ms.record.0))
+            .cloned()
+            .collect();
+        Collection(out)
+    }
+
+    /// Keeps the `(k, v1)` entries of `self` whose key `k` does *not* have positive
+    /// net multiplicity in `other`.
+    pub fn antijoin<V2>(&self, other: &Collection<(K, V2)>) -> Collection<(K, V1)>
+    where
+        V2: Ord + Clone + Eq + Hash,
+    {
+        let present = Self::keys_with_positive_weight(&other.group_by_key());
+        let out = self
+            .0
+            .iter()
+            .filter(|ms| !present.contains(&ms.record.0))
+            .cloned()
+            .collect();
+        Collection(out)
+    }
+
+    fn keys_with_positive_weight<W>(groups: &HashMap<K, Vec<(W, i32)>>) -> std::collections::HashSet<K> {
+        groups
+            .iter()
+            .filter(|(_, values)| values.iter().map(|(_, m)| m).sum::<i32>() > 0)
+            .map(|(key, _)| key.clone())
+            .collect()
+    }
+
+    /// For each key, returns the smallest value associated with that key, ignoring
+    /// values whose net multiplicity (summed across duplicates) is `<= 0` so that
+    /// retracted records can't win. A key with no surviving values produces no output
+    /// row; ties can't occur since equal values are merged (their multiplicities
+    /// summed) before picking the extremum.
+    pub fn min(&self) -> Collection<(K, V1)> {
+        self.extremum_by_key(|values| values.into_iter().min())
+    }
+
+    /// For each key, returns the largest value associated with that key, with the
+    /// same retraction-safe semantics as [`Self::min`].
+    pub fn max(&self) -> Collection<(K, V1)> {
+        self.extremum_by_key(|values| values.into_iter().max())
+    }
+
+    fn extremum_by_key<F>(&self, pick: F) -> Collection<(K, V1)>
+    where
+        F: Fn(Vec<V1>) -> Option<V1>,
+    {
+        let mut out = vec![];
+        for (key, values) in self.group_by_key() {
+            let mut net: HashMap<V1, i32> = HashMap::new();
+            for (value, multiplicity) in values {
+                *net.entry(value).or_insert(0) += multiplicity;
+            }
+            let surviving: Vec<V1> = net
+                .into_iter()
+                .filter(|(_, multiplicity)| *multiplicity > 0)
+                .map(|(value, _)| value)
+                .collect();
+            if let Some(extremum) = pick(surviving) {
+                out.push(MultiSet::new((key, extremum), 1));
+            }
+        }
+        out.sort_unstable_by(|a, b| a.record.cmp(&b.record));
+        Collection(out)
+    }
+
+    /// Groups by key (rather than by whole record, the way `Collection::reduce` does),
+    /// and invokes `logic` once per key with that key's `(value, multiplicity)` pairs,
+    /// flattening its `(result, multiplicity)` pairs back into a `Collection<(K, R)>`.
+    /// `count_by_key` and `distinct_by_key` are built on top of this.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rs_differential_dataflow::collection::Collection;
+    /// use rs_differential_dataflow::multiset::MultiSet;
+    ///
+    /// let coll = Collection(vec![
+    ///     MultiSet::new((1, "a".to_string()), 2),
+    ///     MultiSet::new((1, "b".to_string()), 1),
+    /// ]);
+    /// let result = coll.reduce_by_key(|values| {
+    ///     let total: i32 = values.iter().map(|(_, m)| m).sum();
+    ///     vec![(total, 1)]
+    /// });
+    /// assert_eq!(result, Collection(vec![MultiSet::new((1, 3), 1)]));
+    /// ```
+    pub fn reduce_by_key<R, F>(&self, logic: F) -> Collection<(K, R)>
+    where
+        R: Ord + Clone,
+        F: Fn(Vec<(V1, i32)>) -> Vec<(R, i32)>,
+    {
+        let mut out = vec![];
+        for (key, values) in self.group_by_key() {
+            for (result, multiplicity) in logic(values) {
+                out.push(MultiSet::new((key.clone(), result), multiplicity));
+            }
+        }
+        out.sort_unstable_by(|a, b| a.record.cmp(&b.record));
+        Collection(out)
+    }
+
+    /// Returns each key's total multiplicity as a single `(k, total)` row.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rs_differential_dataflow::collection::Collection;
+    /// use rs_differential_dataflow::multiset::MultiSet;
+    ///
+    /// let coll = Collection(vec![
+    ///     MultiSet::new((1, "a".to_string()), 2),
+    ///     MultiSet::new((1, "b".to_string()), 1),
+    /// ]);
+    /// assert_eq!(coll.count_by_key(), Collection(vec![MultiSet::new((1, 3i64), 1)]));
+    /// ```
+    pub fn count_by_key(&self) -> Collection<(K, i64)> {
+        self.reduce_by_key(|values| {
+            let total: i64 = values.iter().map(|(_, m)| *m as i64).sum();
+            vec![(total, 1)]
+        })
+    }
+
+    /// Returns the distinct values associated with each key, keeping only the values
+    /// whose net multiplicity (summed across duplicates) is strictly positive.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rs_differential_dataflow::collection::Collection;
+    /// use rs_differential_dataflow::multiset::MultiSet;
+    ///
+    /// let coll = Collection(vec![
+    ///     MultiSet::new((1, "a".to_string()), 2),
+    ///     MultiSet::new((1, "a".to_string()), -1),
+    ///     MultiSet::new((1, "b".to_string()), 1),
+    /// ]);
+    /// let mut result = coll.distinct_by_key().0;
+    /// result.sort_unstable_by(|a, b| a.record.cmp(&b.record));
+    /// assert_eq!(result, vec![
+    ///     MultiSet::new((1, "a".to_string()), 1),
+    ///     MultiSet::new((1, "b".to_string()), 1),
+    /// ]);
+    /// ```
+    pub fn distinct_by_key(&self) -> Collection<(K, V1)> {
+        self.reduce_by_key(|values| {
+            let mut net: HashMap<V1, i32> = HashMap::new();
+            for (value, multiplicity) in values {
+                *net.entry(value).or_insert(0) += multiplicity;
+            }
+            net.into_iter()
+                .filter(|(_, multiplicity)| *multiplicity > 0)
+                .map(|(value, _)| (value, 1))
+                .collect()
+        })
+    }
+}
@@ -0,0 +1,98 @@
+/// A join-semilattice over logical timestamps.
+///
+/// `VersionedCollection` requires its `Time` parameter to implement `Lattice` so that
+/// updates from different operators can be compared and merged: `less_equal` answers
+/// "has this update already happened by `other`?" and `least_upper_bound` gives the
+/// earliest time by which both `self` and `other` have happened, which is what lets
+/// `collect_at` accumulate updates along a partial (rather than total) order.
+pub trait Lattice: Clone + PartialEq {
+    /// The smallest element of the lattice; every other element is greater than or
+    /// equal to it.
+    fn minimum() -> Self;
+
+    /// The least element that is greater than or equal to both `self` and `other`.
+    fn least_upper_bound(&self, other: &Self) -> Self;
+
+    /// `true` if `self` happened-before-or-at `other`, i.e. joining the two yields
+    /// `other` unchanged. Has a default implementation in terms of
+    /// `least_upper_bound` so implementors only need to supply that and `minimum`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rs_differential_dataflow::lattice::Lattice;
+    ///
+    /// assert!(Lattice::less_equal(&2u64, &5u64));
+    /// assert!(!Lattice::less_equal(&5u64, &2u64));
+    /// assert_eq!(2u64.least_upper_bound(&5u64), 5u64);
+    /// ```
+    fn less_equal(&self, other: &Self) -> bool {
+        &self.least_upper_bound(other) == other
+    }
+}
+
+macro_rules! impl_lattice_for_integer {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl Lattice for $ty {
+                fn minimum() -> Self {
+                    0
+                }
+
+                fn least_upper_bound(&self, other: &Self) -> Self {
+                    *self.max(other)
+                }
+            }
+        )*
+    };
+}
+
+impl_lattice_for_integer!(u8, u16, u32, u64, usize);
+
+impl<A: Lattice, B: Lattice> Lattice for (A, B) {
+    fn minimum() -> Self {
+        (A::minimum(), B::minimum())
+    }
+
+    fn least_upper_bound(&self, other: &Self) -> Self {
+        (
+            self.0.least_upper_bound(&other.0),
+            self.1.least_upper_bound(&other.1),
+        )
+    }
+}
+
+/// The least upper bound of two vectors of versions is their componentwise max,
+/// padding the shorter vector with `Time::minimum()` so both sides line up.
+///
+/// # Examples
+///
+/// ```
+/// use rs_differential_dataflow::lattice::Lattice;
+///
+/// // Padding: a shorter vector is treated as if its missing components are `minimum()`.
+/// let a = vec![1u64, 5];
+/// let b = vec![3u64];
+/// assert_eq!(a.least_upper_bound(&b), vec![3u64, 5]);
+///
+/// // Neither `[1, 5]` nor `[3, 0]` happened-before the other, so this is a genuine
+/// // partial order, not a total one.
+/// assert!(!a.less_equal(&vec![3u64, 0]));
+/// assert!(!vec![3u64, 0].less_equal(&a));
+/// ```
+impl<Time: Lattice> Lattice for Vec<Time> {
+    fn minimum() -> Self {
+        Vec::new()
+    }
+
+    fn least_upper_bound(&self, other: &Self) -> Self {
+        let len = self.len().max(other.len());
+        (0..len)
+            .map(|i| {
+                let lhs = self.get(i).cloned().unwrap_or_else(Time::minimum);
+                let rhs = other.get(i).cloned().unwrap_or_else(Time::minimum);
+                lhs.least_upper_bound(&rhs)
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,288 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::lattice::Lattice;
+
+/// A collection whose updates are stamped with a logical time instead of being
+/// applied all at once.
+///
+/// Each entry is a `(record, time, diff)` triple: `record` changed by `diff` as of
+/// `time`. Querying the collection as of a given time (`collect_at`) accumulates every triple whose
+/// `time` is less-or-equal (in `Time`'s partial order) to the query time, which is
+/// what lets operators recompute incrementally instead of from scratch.
+///
+/// This is a deliberately separate type rather than a `time` field bolted onto
+/// [`crate::multiset::MultiSet`]/[`crate::collection::Collection`]: those stay a flat,
+/// untimestamped multiset, and `VersionedCollection<T, Time>` is the timestamped
+/// counterpart with its own `map`/`filter`/`concat`/`negate`/`reduce`/`distinct`, keyed
+/// on `Time: Lattice` instead of a total order. This supersedes the originally-requested
+/// `Collection::at(time)` shape: `collect_at` is the one query entry point (there is no
+/// separate `at`, to avoid two names for the same operation on this type).
+#[derive(Debug, Clone)]
+pub struct VersionedCollection<T, Time: Lattice>(pub Vec<(T, Time, i32)>);
+
+impl<T: Clone, Time: Lattice> VersionedCollection<T, Time> {
+    /// Instantiates a new `VersionedCollection<T, Time>` from a vec of `(record, time, diff)`
+    /// triples.
+    pub fn new(updates: Vec<(T, Time, i32)>) -> VersionedCollection<T, Time> {
+        VersionedCollection(updates)
+    }
+
+    /// Accumulates every update whose time is `<=` `query` in the lattice's partial
+    /// order, summing diffs per record and dropping records whose net diff is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rs_differential_dataflow::versioned::VersionedCollection;
+    ///
+    /// let coll = VersionedCollection::new(vec![
+    ///     ("a", 1u64, 1),
+    ///     ("a", 2u64, 1),
+    ///     ("a", 3u64, -2),
+    ///     ("b", 2u64, 1),
+    /// ]);
+    ///
+    /// // At time 1, only the first update has happened.
+    /// assert_eq!(coll.collect_at(&1), vec![("a", 1)]);
+    ///
+    /// // At time 2, "a" has accumulated to 2 and "b" has appeared.
+    /// let mut at_two = coll.collect_at(&2);
+    /// at_two.sort_unstable();
+    /// assert_eq!(at_two, vec![("a", 2), ("b", 1)]);
+    ///
+    /// // At time 3, "a"'s retraction cancels it out of the result entirely.
+    /// assert_eq!(coll.collect_at(&3), vec![("b", 1)]);
+    /// ```
+    pub fn collect_at(&self, query: &Time) -> Vec<(T, i32)>
+    where
+        T: Eq + Hash,
+    {
+        let mut totals: HashMap<T, i32> = HashMap::new();
+        for (record, time, diff) in &self.0 {
+            if time.less_equal(query) {
+                *totals.entry(record.clone()).or_insert(0) += diff;
+            }
+        }
+        totals.into_iter().filter(|(_, diff)| *diff != 0).collect()
+    }
+
+    /// Combines two versioned collections, keeping every `(record, time, diff)` triple
+    /// from both. Mirrors `Collection::concat`.
+    pub fn concat(self, other: VersionedCollection<T, Time>) -> VersionedCollection<T, Time> {
+        let mut out = self.0;
+        out.extend(other.0);
+        VersionedCollection(out)
+    }
+
+    /// Flips the sign of every diff, leaving the times untouched. Mirrors
+    /// `Collection::negate`.
+    pub fn negate(self) -> VersionedCollection<T, Time> {
+        let out = self
+            .0
+            .into_iter()
+            .map(|(record, time, diff)| (record, time, -diff))
+            .collect();
+        VersionedCollection(out)
+    }
+
+    /// Applies `f` to the record of every triple, carrying its `(time, diff)` through
+    /// unchanged. Mirrors `Collection::map`.
+    pub fn map<U, F>(&self, f: F) -> VersionedCollection<U, Time>
+    where
+        F: Fn(&T) -> U,
+    {
+        let out = self
+            .0
+            .iter()
+            .map(|(record, time, diff)| (f(record), time.clone(), *diff))
+            .collect();
+        VersionedCollection(out)
+    }
+
+    /// Keeps every triple whose record satisfies `f`, carrying its `(time, diff)`
+    /// through unchanged. Mirrors `Collection::filter`.
+    pub fn filter<F>(&self, f: F) -> VersionedCollection<T, Time>
+    where
+        F: Fn(&T) -> bool,
+    {
+        let out = self
+            .0
+            .iter()
+            .filter(|(record, _, _)| f(record))
+            .cloned()
+            .collect();
+        VersionedCollection(out)
+    }
+}
+
+impl<T: Clone + Eq + Hash, Time: Lattice> VersionedCollection<T, Time> {
+    /// Groups triples by key, and for each key and each distinct time present in that
+    /// key's history, recomputes `f` over the values accumulated as of that time and
+    /// emits the *difference* from the previous time as a new diff. This is what lets
+    /// `reduce` stay incremental instead of recomputing every output from scratch on
+    /// every change.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rs_differential_dataflow::versioned::VersionedCollection;
+    ///
+    /// // Two "a"s arrive at time 1, a third arrives at time 2.
+    /// let coll = VersionedCollection::new(vec![
+    ///     ("a".to_string(), 1u64, 1),
+    ///     ("a".to_string(), 1u64, 1),
+    ///     ("a".to_string(), 2u64, 1),
+    /// ]);
+    ///
+    /// let counts = coll.reduce(|vals| {
+    ///     let total: i32 = vals.iter().map(|(_, diff)| diff).sum();
+    ///     vec![(vals[0].0.clone(), total)]
+    /// });
+    ///
+    /// // `reduce` emitted a +2 diff at time 1 and a +1 diff at time 2 -- the
+    /// // difference from the previous round, not a recomputed total each time.
+    /// assert_eq!(counts.collect_at(&1), vec![("a".to_string(), 2)]);
+    /// assert_eq!(counts.collect_at(&2), vec![("a".to_string(), 3)]);
+    /// ```
+    pub fn reduce<F>(&self, f: F) -> VersionedCollection<T, Time>
+    where
+        F: Fn(Vec<(T, i32)>) -> Vec<(T, i32)>,
+        Time: Ord,
+    {
+        let mut by_key: HashMap<T, Vec<(Time, i32)>> = HashMap::new();
+        for (record, time, diff) in &self.0 {
+            by_key
+                .entry(record.clone())
+                .or_default()
+                .push((time.clone(), *diff));
+        }
+
+        let mut out = vec![];
+        for (key, mut history) in by_key {
+            history.sort_by(|a, b| a.0.cmp(&b.0));
+
+            let mut times: Vec<Time> = history.iter().map(|(t, _)| t.clone()).collect();
+            times.dedup();
+
+            let mut previous: HashMap<T, i32> = HashMap::new();
+            for time in times {
+                let accumulated: Vec<(T, i32)> = history
+                    .iter()
+                    .filter(|(t, _)| t.less_equal(&time))
+                    .map(|(_, diff)| (key.clone(), *diff))
+                    .collect();
+                let current: HashMap<T, i32> =
+                    f(accumulated).into_iter().collect();
+
+                for (record, diff) in &current {
+                    let prior = previous.get(record).copied().unwrap_or(0);
+                    if *diff != prior {
+                        out.push((record.clone(), time.clone(), diff - prior));
+                    }
+                }
+                for (record, prior) in &previous {
+                    if !current.contains_key(record) && *prior != 0 {
+                        out.push((record.clone(), time.clone(), -prior));
+                    }
+                }
+
+                previous = current;
+            }
+        }
+
+        VersionedCollection(out)
+    }
+
+    /// At each time a record's accumulated weight changes, emits that record with
+    /// diff `+1`/`-1` as it crosses from non-positive to positive weight or back,
+    /// the timed analogue of `Collection::distinct`. Built on `reduce`, so it inherits
+    /// its incremental, emit-the-difference behavior instead of recomputing the full
+    /// distinct set at every time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rs_differential_dataflow::versioned::VersionedCollection;
+    ///
+    /// // "a" arrives twice at time 1 (still one distinct record), then is fully
+    /// // retracted at time 2.
+    /// let coll = VersionedCollection::new(vec![
+    ///     ("a".to_string(), 1u64, 1),
+    ///     ("a".to_string(), 1u64, 1),
+    ///     ("a".to_string(), 2u64, -2),
+    /// ]);
+    ///
+    /// let distinct = coll.distinct();
+    /// assert_eq!(distinct.collect_at(&1), vec![("a".to_string(), 1)]);
+    /// assert_eq!(distinct.collect_at(&2), vec![]);
+    /// ```
+    pub fn distinct(&self) -> VersionedCollection<T, Time>
+    where
+        Time: Ord,
+    {
+        self.reduce(|vals| {
+            let total: i32 = vals.iter().map(|(_, diff)| diff).sum();
+            match vals.first() {
+                Some((record, _)) if total > 0 => vec![(record.clone(), 1)],
+                _ => vec![],
+            }
+        })
+    }
+
+    /// Merges triples that share both a record and a time, summing their diffs and
+    /// dropping any `(record, time)` pair whose net diff is zero. The timed analogue
+    /// of `Collection::consolidate`.
+    pub fn consolidate(&self) -> VersionedCollection<T, Time>
+    where
+        Time: Eq + Hash,
+    {
+        let mut totals: HashMap<(T, Time), i32> = HashMap::new();
+        for (record, time, diff) in &self.0 {
+            *totals.entry((record.clone(), time.clone())).or_insert(0) += diff;
+        }
+        let out = totals
+            .into_iter()
+            .filter(|(_, diff)| *diff != 0)
+            .map(|((record, time), diff)| (record, time, diff))
+            .collect();
+        VersionedCollection(out)
+    }
+
+    /// Repeatedly applies `f` to the current collection, stamping each round's output
+    /// with the next time produced by `advance` rather than comparing the whole
+    /// collection for equality: a round is a fixpoint once what it accumulates
+    /// (`collect_at`) at its time matches what the previous round accumulated at its own time.
+    /// Runs for at most `max_iters` rounds, returning the last round's collection if
+    /// `f` never converges.
+    pub fn iterate<F, Adv>(&self, start: Time, advance: Adv, f: F, max_iters: usize) -> VersionedCollection<T, Time>
+    where
+        F: Fn(&VersionedCollection<T, Time>) -> Vec<(T, i32)>,
+        Adv: Fn(&Time) -> Time,
+        Time: Eq + Hash,
+    {
+        let mut curr = self.clone();
+        let mut time = start;
+        let mut previous: HashMap<T, i32> = curr.collect_at(&time).into_iter().collect();
+
+        for _ in 0..max_iters {
+            let next_time = advance(&time);
+            let produced = f(&curr);
+            let mut next = curr.clone();
+            next.0
+                .extend(produced.into_iter().map(|(record, diff)| (record, next_time.clone(), diff)));
+            let next = next.consolidate();
+
+            let at_next: HashMap<T, i32> = next.collect_at(&next_time).into_iter().collect();
+            if at_next == previous {
+                return next;
+            }
+
+            curr = next;
+            time = next_time;
+            previous = at_next;
+        }
+
+        curr
+    }
+}
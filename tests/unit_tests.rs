@@ -0,0 +1,9 @@
+// Cargo only compiles top-level `tests/*.rs` files into test binaries, so the
+// per-module test files under `tests/unit_tests/` need to be pulled in from here to
+// actually run.
+#[path = "unit_tests/multiset_tests.rs"]
+mod multiset_tests;
+#[path = "unit_tests/collection_tests.rs"]
+mod collection_tests;
+#[path = "unit_tests/keyed_tests.rs"]
+mod keyed_tests;
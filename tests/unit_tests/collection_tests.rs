@@ -0,0 +1,71 @@
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_consolidate_sums_and_drops_zero_totals() {
+        use rs_differential_dataflow::collection::Collection;
+        use rs_differential_dataflow::multiset::MultiSet;
+
+        let coll = Collection(vec![
+            MultiSet::new("a".to_string(), 1),
+            MultiSet::new("b".to_string(), 0),
+            MultiSet::new("a".to_string(), -1),
+            MultiSet::new("a".to_string(), 2),
+        ]);
+
+        assert_eq!(
+            coll.consolidate(),
+            Collection(vec![MultiSet::new("a".to_string(), 2)])
+        );
+    }
+
+    #[test]
+    fn test_consolidate_preserves_negative_totals() {
+        use rs_differential_dataflow::collection::Collection;
+        use rs_differential_dataflow::multiset::MultiSet;
+
+        let coll = Collection(vec![
+            MultiSet::new("a".to_string(), 1),
+            MultiSet::new("a".to_string(), -3),
+        ]);
+
+        assert_eq!(
+            coll.consolidate(),
+            Collection(vec![MultiSet::new("a".to_string(), -2)])
+        );
+    }
+
+    #[test]
+    fn test_negate_and_concat_express_subtraction() {
+        use rs_differential_dataflow::collection::Collection;
+        use rs_differential_dataflow::multiset::MultiSet;
+
+        let a = Collection(vec![
+            MultiSet::new(1, 2),
+            MultiSet::new(2, 1),
+        ]);
+        let b = Collection(vec![MultiSet::new(1, 1)]);
+
+        let difference = a.concat(b.negate()).consolidate();
+        assert_eq!(
+            difference,
+            Collection(vec![MultiSet::new(1, 1), MultiSet::new(2, 1)])
+        );
+    }
+
+    #[test]
+    fn test_distinct_drops_records_retracted_to_zero_or_below() {
+        use rs_differential_dataflow::collection::Collection;
+        use rs_differential_dataflow::multiset::MultiSet;
+
+        let coll = Collection(vec![
+            MultiSet::new("a".to_string(), 1),
+            MultiSet::new("a".to_string(), -1),
+            MultiSet::new("b".to_string(), 1),
+        ]);
+
+        assert_eq!(
+            coll.distinct(),
+            Collection(vec![MultiSet::new("b".to_string(), 1)])
+        );
+    }
+}
@@ -0,0 +1,40 @@
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_join_multiplies_and_sums_multiplicities() {
+        use rs_differential_dataflow::collection::Collection;
+        use rs_differential_dataflow::multiset::MultiSet;
+
+        let left = Collection(vec![
+            MultiSet::new((1, "a".to_string()), 2),
+            MultiSet::new((1, "b".to_string()), 1),
+        ]);
+        let right = Collection(vec![MultiSet::new((1, "x".to_string()), 3)]);
+
+        let result = left.join(&right);
+        assert_eq!(
+            result,
+            Collection(vec![
+                MultiSet::new((1, ("a".to_string(), "x".to_string())), 6),
+                MultiSet::new((1, ("b".to_string(), "x".to_string())), 3),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_join_propagates_retractions_through_sign() {
+        use rs_differential_dataflow::collection::Collection;
+        use rs_differential_dataflow::multiset::MultiSet;
+
+        // A retraction (multiplicity -1) on one side should flip the sign of the
+        // joined output rather than being ignored or treated as absence.
+        let left = Collection(vec![MultiSet::new((1, "a".to_string()), -1)]);
+        let right = Collection(vec![MultiSet::new((1, "x".to_string()), 2)]);
+
+        let result = left.join(&right);
+        assert_eq!(
+            result,
+            Collection(vec![MultiSet::new((1, ("a".to_string(), "x".to_string())), -2)])
+        );
+    }
+}